@@ -0,0 +1,96 @@
+//! Downloads Roblox decal/texture assets so parts using them keep their
+//! look in the converted map instead of silently losing their material.
+use std::fmt;
+use std::path::PathBuf;
+
+use async_std::fs;
+
+use crate::vtf;
+
+#[derive(Debug)]
+pub enum AssetError {
+    Offline(u64),
+    Request(surf::Error),
+    Decode(image::ImageError),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetError::Offline(id) => write!(f, "asset {} is not cached and --offline was passed", id),
+            AssetError::Request(error) => write!(f, "could not download asset: {}", error),
+            AssetError::Decode(error) => write!(f, "could not decode downloaded asset as an image: {}", error),
+            AssetError::Io(error) => write!(f, "could not access asset cache: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+impl From<std::io::Error> for AssetError {
+    fn from(error: std::io::Error) -> Self {
+        AssetError::Io(error)
+    }
+}
+
+/// On-disk cache of already-downloaded-and-converted decal VTFs, keyed by
+/// Roblox asset ID, so re-running a conversion (or converting a map that
+/// reuses the same decal on many parts) doesn't redownload each time.
+pub struct AssetCache {
+    dir: PathBuf,
+}
+
+impl AssetCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        AssetCache { dir: dir.into() }
+    }
+
+    fn path_for(&self, asset_id: u64) -> PathBuf {
+        self.dir.join(format!("{}.vtf", asset_id))
+    }
+
+    async fn read(&self, asset_id: u64) -> Option<Vec<u8>> {
+        fs::read(self.path_for(asset_id)).await.ok()
+    }
+
+    async fn write(&self, asset_id: u64, vtf_bytes: &[u8]) -> Result<(), AssetError> {
+        fs::create_dir_all(&self.dir).await?;
+        fs::write(self.path_for(asset_id), vtf_bytes).await?;
+        Ok(())
+    }
+}
+
+/// Resolves `asset_id` through the Roblox asset-delivery endpoint (optionally
+/// fronted by `cors_proxy_base`, Web builds need this since the delivery
+/// endpoint doesn't send CORS headers), downloads the image, resizes it to
+/// `decal_size` square, and returns it encoded as a VTF. Cached results under
+/// `cache` are preferred over a network round-trip; with `offline` set, a
+/// cache miss is an error rather than a fetch attempt.
+pub async fn fetch_decal_vtf(
+    asset_id: u64,
+    cors_proxy_base: &str,
+    decal_size: u32,
+    cache: &AssetCache,
+    offline: bool,
+) -> Result<Vec<u8>, AssetError> {
+    if let Some(cached) = cache.read(asset_id).await {
+        return Ok(cached);
+    }
+    if offline {
+        return Err(AssetError::Offline(asset_id));
+    }
+
+    let url = format!(
+        "{}https://assetdelivery.roblox.com/v1/asset/?id={}",
+        cors_proxy_base, asset_id
+    );
+    let bytes = surf::get(url).recv_bytes().await.map_err(AssetError::Request)?;
+    let image = image::load_from_memory(&bytes).map_err(AssetError::Decode)?;
+    let resized = image.resize_exact(decal_size, decal_size, image::imageops::FilterType::Lanczos3);
+    let rgba = resized.to_rgba8();
+
+    let vtf_bytes = vtf::encode_rgba8888(decal_size as u16, decal_size as u16, rgba.as_raw());
+    cache.write(asset_id, &vtf_bytes).await?;
+    Ok(vtf_bytes)
+}
@@ -0,0 +1,76 @@
+//! Per-game conversion settings, loadable from TOML so a new Source mod (or a
+//! different content set for an existing one) doesn't need a recompile.
+//!
+//! The games `main.rs` already knows about ship as embedded default
+//! profiles; `--game-profile <FILE.toml>` loads one from disk instead. A
+//! loaded profile replaces the builtin one whole; its fields are not merged
+//! with those of a builtin profile.
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GameProfile {
+    pub skybox_name: String,
+    /// Roblox material key (e.g. `"Plastic"`, `"Custom:studs"`) to a VTF file
+    /// path, overriding the embedded default for that material.
+    #[serde(default)]
+    pub material_overrides: HashMap<String, String>,
+    #[serde(default = "GameProfile::default_scale")]
+    pub scale: f64,
+}
+
+impl GameProfile {
+    pub fn default_scale() -> f64 {
+        15.0
+    }
+
+    /// The profile for one of the games `main.rs` has always shipped with.
+    pub fn builtin(game: &str) -> Option<GameProfile> {
+        let skybox_name = match game {
+            "css" => "sky_day01_05",
+            "csgo" => "sky_day02_05",
+            "gmod" => "painted",
+            "hl2" => "sky_day01_04",
+            "hl2e1" => "sky_ep01_01",
+            "hl2e2" => "sky_ep02_01_hdr",
+            "hl" => "city",
+            "hls" => "sky_wasteland02",
+            "l4d" => "river_hdr",
+            "l4d2" => "sky_l4d_c1_2_hdr",
+            "portal2" => "sky_day01_01",
+            "portal" => "sky_day01_05_hdr",
+            "tf2" => "sky_day01_01",
+            _ => return None,
+        };
+        Some(GameProfile {
+            skybox_name: skybox_name.to_owned(),
+            material_overrides: HashMap::new(),
+            scale: Self::default_scale(),
+        })
+    }
+
+    pub fn load_from_file(path: &Path) -> Result<GameProfile, GameProfileError> {
+        let contents = std::fs::read_to_string(path).map_err(GameProfileError::Io)?;
+        toml::from_str(&contents).map_err(GameProfileError::Parse)
+    }
+}
+
+#[derive(Debug)]
+pub enum GameProfileError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+}
+
+impl fmt::Display for GameProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameProfileError::Io(error) => write!(f, "could not read game profile: {}", error),
+            GameProfileError::Parse(error) => write!(f, "could not parse game profile TOML: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for GameProfileError {}
@@ -1,18 +1,101 @@
 #![allow(non_snake_case)]
 #![feature(try_blocks)]
 
+use std::cell::RefCell;
 use std::ffi::OsStr;
 use std::fs::{File, Metadata};
 use std::io::{Read, Write};
 use std::path::Path;
 use std::process::ExitCode;
+use std::rc::Rc;
 use clap::{App, Arg};
 use crate::conv::{ConvertOptions, OwnedOrMut, OwnedOrRef};
 use crate::rbx::Material;
+use crate::report::{Report, SkipCategory};
+use crate::game_profile::GameProfile;
 
 mod rbx;
 mod vmf;
 mod conv;
+mod rbx_binary;
+mod vtf;
+// Pulls in native-only I/O (`async_std::fs`, `surf`'s native HTTP client)
+// that has no wasm32-unknown-unknown backend; the WASM build fetches decal
+// assets itself instead, without a cache (see wasm.rs).
+#[cfg(not(target_arch = "wasm32"))]
+mod assets;
+mod report;
+mod package;
+mod game_profile;
+#[cfg(target_arch = "wasm32")]
+mod wasm;
+
+/// Shared with the WASM build so both entry points agree on which Source
+/// texture backs a given Roblox material.
+fn texture_bytes_for(texture: Material) -> Option<&'static [u8]> {
+    Some(match texture {
+        Material::Plastic => crate::rbx::textures::PLASTIC,
+        Material::Wood => crate::rbx::textures::WOOD,
+        Material::Slate => crate::rbx::textures::SLATE,
+        Material::Concrete => crate::rbx::textures::CONCRETE,
+        Material::CorrodedMetal => crate::rbx::textures::RUST,
+        Material::DiamondPlate => crate::rbx::textures::DIAMONDPLATE,
+        Material::Foil => crate::rbx::textures::ALUMINIUM,
+        Material::Grass => crate::rbx::textures::GRASS,
+        Material::Ice => crate::rbx::textures::ICE,
+        Material::Marble => crate::rbx::textures::MARBLE,
+        Material::Granite => crate::rbx::textures::GRANITE,
+        Material::Brick => crate::rbx::textures::BRICK,
+        Material::Pebble => crate::rbx::textures::PEBBLE,
+        Material::Sand => crate::rbx::textures::SAND,
+        Material::Fabric => crate::rbx::textures::FABRIC,
+        Material::SmoothPlastic => crate::rbx::textures::SMOOTHPLASTIC,
+        Material::Metal => crate::rbx::textures::METAL,
+        Material::WoodPlanks => crate::rbx::textures::WOODPLANKS,
+        Material::Cobblestone => crate::rbx::textures::COBBLESTONE,
+        Material::Glass => crate::rbx::textures::GLASS,
+        Material::ForceField => crate::rbx::textures::FORCEFIELD,
+        Material::Custom { texture: "decal", .. } => crate::rbx::textures::DECAL,
+        Material::Custom { texture: "studs", .. } => crate::rbx::textures::STUDS,
+        Material::Custom { texture: "inlet", .. } => crate::rbx::textures::INLET,
+        Material::Custom { texture: "spawnlocation", .. } => crate::rbx::textures::SPAWNLOCATION,
+        Material::Custom { .. } | Material::Decal { .. } | Material::Texture { .. } => return None,
+    })
+}
+
+/// Stable string key identifying a material for `GameProfile::material_overrides`,
+/// independent of whether it came from a builtin Roblox material or a `Custom` one.
+fn material_key(texture: &Material) -> Option<String> {
+    Some(match texture {
+        Material::Custom { texture, .. } => format!("Custom:{}", texture),
+        Material::Decal { .. } | Material::Texture { .. } => return None,
+        other => format!("{:?}", other),
+    })
+}
+
+/// Loads the profile for `--game-profile` if given, falling back to the
+/// embedded default for `--game`. The only guard against an unknown `--game`
+/// value here is clap's `possible_values`, but as we're (also) a clientside
+/// application, just substitute in a placeholder skybox.
+fn resolve_game_profile(matches: &clap::ArgMatches) -> GameProfile {
+    if let Some(path) = matches.value_of("game-profile") {
+        match GameProfile::load_from_file(Path::new(path)) {
+            Ok(profile) => return profile,
+            Err(error) => {
+                println!("error: {}", error);
+                std::process::exit(-1)
+            }
+        }
+    }
+    matches
+        .value_of("game")
+        .and_then(GameProfile::builtin)
+        .unwrap_or_else(|| GameProfile {
+            skybox_name: "default_skybox_fixme".to_owned(),
+            material_overrides: std::collections::HashMap::new(),
+            scale: GameProfile::default_scale(),
+        })
+}
 
 fn main() -> ExitCode {
     let matches = App::new("RBXLX2VMF")
@@ -68,16 +151,47 @@ fn main() -> ExitCode {
             .help("sets downloaded decal texture size")
             .default_value("256")
             .takes_value(true))
+        .arg(Arg::with_name("package")
+            .long("package")
+            .value_name("FILE.zip")
+            .help("bundles the VMF and generated textures into a single zip, under materials/ and mapsrc/")
+            .takes_value(true))
+        .arg(Arg::with_name("report")
+            .long("report")
+            .value_name("FILE")
+            .help("writes a JSON manifest of skipped/unsupported instances")
+            .takes_value(true))
+        .arg(Arg::with_name("offline")
+            .long("offline")
+            .help("skip downloading decal/texture assets; only cached ones are used")
+            .takes_value(false))
+        .arg(Arg::with_name("asset-proxy")
+            .long("asset-proxy")
+            .value_name("URL")
+            .help("CORS proxy base URL to prepend to asset-delivery requests (for WASM builds)")
+            .default_value("")
+            .takes_value(true))
         .arg(Arg::with_name("game")
             .long("game")
             .short("g")
             .help("sets target source engine game")
-            .required(true)
             .takes_value(true)
             .possible_values(&["css", "csgo", "gmod", "hl2", "hl2e1", "hl2e2", "hl", "hls", "l4d", "l4d2", "portal2", "portal", "tf2"])
         )
+        .arg(Arg::with_name("game-profile")
+            .long("game-profile")
+            .value_name("FILE.toml")
+            .help("loads a GameProfile (skybox, material overrides, scale) from TOML, overriding --game")
+            .takes_value(true))
         .get_matches();
 
+    if matches.value_of("game").is_none() && matches.value_of("game-profile").is_none() {
+        println!("error: one of --game or --game-profile is required");
+        std::process::exit(-1)
+    }
+    let game_profile = resolve_game_profile(&matches);
+
+    let report = Rc::new(RefCell::new(Report::new()));
     let exit_code = async_std::task::block_on(
         conv::convert(CLIConvertOptions {
             input_name: matches.value_of("input").unwrap(),
@@ -93,11 +207,15 @@ fn main() -> ExitCode {
             },
             is_texture_output_enabled: !matches.is_present("no-textures"),
             use_developer_textures: matches.is_present("dev-textures"),
-            map_scale: match matches.value_of("map-scale").unwrap().parse() {
-                Ok(f) => f,
-                Err(_) => {
-                    println!("error: invalid map scale");
-                    std::process::exit(-1)
+            map_scale: if matches.occurrences_of("map-scale") == 0 {
+                game_profile.scale
+            } else {
+                match matches.value_of("map-scale").unwrap().parse() {
+                    Ok(f) => f,
+                    Err(_) => {
+                        println!("error: invalid map scale");
+                        std::process::exit(-1)
+                    }
                 }
             },
             auto_skybox_enabled: matches.is_present("auto-skybox"),
@@ -110,25 +228,42 @@ fn main() -> ExitCode {
                     std::process::exit(-1)
                 }
             },
-            skybox_name: match matches.value_of("game").unwrap() {
-                "css" => "sky_day01_05",
-                "csgo" => "sky_day02_05",
-                "gmod" => "painted",
-                "hl2" => "sky_day01_04",
-                "hl2e1" => "sky_ep01_01",
-                "hl2e2" => "sky_ep02_01_hdr",
-                "hl" => "city",
-                "hls" => "sky_wasteland02",
-                "l4d" => "river_hdr",
-                "l4d2" => "sky_l4d_c1_2_hdr",
-                "portal2" => "sky_day01_01",
-                "portal" => "sky_day01_05_hdr",
-                "tf2" => "sky_day01_01",
-                _ => "default_skybox_fixme" // The only guard against invalid values here is HTML form validation, but as we're a clientside application, just substitute in a placeholder value
-            }
+            skybox_name: game_profile.skybox_name.clone(),
+            offline: matches.is_present("offline"),
+            asset_proxy: matches.value_of("asset-proxy").unwrap().to_owned(),
+            asset_cache: assets::AssetCache::new(Path::new(matches.value_of_os("texture-output").unwrap()).join(package::ASSET_CACHE_DIR)),
+            report: report.clone(),
+            material_override_cache: RefCell::new(std::collections::HashMap::new()),
+            game_profile,
         })
     );
 
+    if let Err(error) = report.borrow().write_summary(&mut std::io::stdout()) {
+        eprintln!("error: could not print conversion report: {}", error);
+    }
+    if let Some(report_path) = matches.value_of("report") {
+        match File::create(report_path) {
+            Ok(file) => {
+                if let Err(error) = report.borrow().write_json(&mut std::io::BufWriter::new(file)) {
+                    eprintln!("error: could not write conversion report to {}: {}", report_path, error);
+                }
+            }
+            Err(error) => eprintln!("error: could not create report file {}: {}", report_path, error),
+        }
+    }
+
+    if exit_code.is_ok() {
+        if let Some(package_path) = matches.value_of("package") {
+            if let Err(error) = write_package(
+                package_path,
+                matches.value_of_os("output").unwrap(),
+                Path::new(matches.value_of_os("texture-output").unwrap()),
+            ) {
+                eprintln!("error: could not write package {}: {}", package_path, error);
+            }
+        }
+    }
+
     return match exit_code {
         Ok(code) => ExitCode::from(code),
         // Error writing to STDIO
@@ -139,6 +274,17 @@ fn main() -> ExitCode {
     }
 }
 
+fn write_package(package_path: &str, output_path: &OsStr, texture_output_folder: &Path) -> std::io::Result<()> {
+    let vmf_bytes = std::fs::read(output_path)?;
+    let vmf_name = Path::new(output_path).file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_else(|| "rbxlx_out.vmf".to_owned());
+    let textures = package::collect_texture_tree(texture_output_folder)?;
+
+    let file = File::create(package_path)?;
+    package::build(file, &vmf_name, &vmf_bytes, textures)
+        .map_err(|error| std::io::Error::new(std::io::ErrorKind::Other, error))?;
+    Ok(())
+}
+
 struct CLIConvertOptions<'a> {
     input_name: &'a str,
     input_path: &'a OsStr,
@@ -151,7 +297,13 @@ struct CLIConvertOptions<'a> {
     skybox_clearance: f64,
     optimization_enabled: bool,
     decal_size: u64,
-    skybox_name: &'a str
+    skybox_name: String,
+    offline: bool,
+    asset_proxy: String,
+    asset_cache: assets::AssetCache,
+    report: Rc<RefCell<Report>>,
+    material_override_cache: RefCell<std::collections::HashMap<String, &'static [u8]>>,
+    game_profile: GameProfile,
 }
 
 impl<'a> ConvertOptions<&'static [u8], File> for CLIConvertOptions<'a> {
@@ -174,14 +326,42 @@ impl<'a> ConvertOptions<&'static [u8], File> for CLIConvertOptions<'a> {
                 std::process::exit(-1)
             }
         };
-        let mut buffer = String::with_capacity(file.metadata().as_ref().map(Metadata::len).unwrap_or(0) as usize);
-        match file.read_to_string(&mut buffer) {
-            Ok(_) => {}
-            Err(error) => {
-                println!("error: Could not read input {}", error);
-                std::process::exit(-1)
-            }
+        let mut raw = Vec::with_capacity(file.metadata().as_ref().map(Metadata::len).unwrap_or(0) as usize);
+        if let Err(error) = file.read_to_end(&mut raw) {
+            println!("error: Could not read input {}", error);
+            std::process::exit(-1)
         }
+
+        let buffer = if rbx_binary::is_binary(&raw) {
+            match rbx_binary::decode_to_xml(&raw) {
+                Ok((xml, unresolved_rotations)) => {
+                    for unresolved in unresolved_rotations {
+                        println!(
+                            "warning: instance {} has a CFrame.{} with unrecognized rotation id {}, defaulted to identity",
+                            unresolved.referent, unresolved.property, unresolved.rotation_id
+                        );
+                        self.report.borrow_mut().record(
+                            SkipCategory::UnresolvedRotation,
+                            format!("instance {} ({})", unresolved.referent, unresolved.property),
+                            Some(format!("rotation id {}", unresolved.rotation_id)),
+                        );
+                    }
+                    xml
+                }
+                Err(error) => {
+                    println!("error: Could not parse binary place file: {}", error);
+                    std::process::exit(-1)
+                }
+            }
+        } else {
+            match String::from_utf8(raw) {
+                Ok(text) => text,
+                Err(error) => {
+                    println!("error: Input is not valid UTF-8 XML nor a binary place file: {}", error);
+                    std::process::exit(-1)
+                }
+            }
+        };
         OwnedOrRef::Owned(buffer)
     }
 
@@ -196,34 +376,57 @@ impl<'a> ConvertOptions<&'static [u8], File> for CLIConvertOptions<'a> {
     }
 
     fn texture_input(&mut self, texture: Material) -> Option<OwnedOrMut<'_, &'static [u8]>> {
-        Some(OwnedOrMut::Owned(match texture {
-            Material::Plastic => crate::rbx::textures::PLASTIC,
-            Material::Wood => crate::rbx::textures::WOOD,
-            Material::Slate => crate::rbx::textures::SLATE,
-            Material::Concrete => crate::rbx::textures::CONCRETE,
-            Material::CorrodedMetal => crate::rbx::textures::RUST,
-            Material::DiamondPlate => crate::rbx::textures::DIAMONDPLATE,
-            Material::Foil => crate::rbx::textures::ALUMINIUM,
-            Material::Grass => crate::rbx::textures::GRASS,
-            Material::Ice => crate::rbx::textures::ICE,
-            Material::Marble => crate::rbx::textures::MARBLE,
-            Material::Granite => crate::rbx::textures::GRANITE,
-            Material::Brick => crate::rbx::textures::BRICK,
-            Material::Pebble => crate::rbx::textures::PEBBLE,
-            Material::Sand => crate::rbx::textures::SAND,
-            Material::Fabric => crate::rbx::textures::FABRIC,
-            Material::SmoothPlastic => crate::rbx::textures::SMOOTHPLASTIC,
-            Material::Metal => crate::rbx::textures::METAL,
-            Material::WoodPlanks => crate::rbx::textures::WOODPLANKS,
-            Material::Cobblestone => crate::rbx::textures::COBBLESTONE,
-            Material::Glass => crate::rbx::textures::GLASS,
-            Material::ForceField => crate::rbx::textures::FORCEFIELD,
-            Material::Custom { texture: "decal", .. } => crate::rbx::textures::DECAL,
-            Material::Custom { texture: "studs", .. } => crate::rbx::textures::STUDS,
-            Material::Custom { texture: "inlet", .. } => crate::rbx::textures::INLET,
-            Material::Custom { texture: "spawnlocation", .. } => crate::rbx::textures::SPAWNLOCATION,
-            Material::Custom { .. } | Material::Decal { .. } | Material::Texture { .. } => return None,
-        }))
+        if let Some(key) = material_key(&texture) {
+            if let Some(&cached) = self.material_override_cache.borrow().get(&key) {
+                return Some(OwnedOrMut::Owned(cached));
+            }
+            if let Some(override_path) = self.game_profile.material_overrides.get(&key) {
+                return match std::fs::read(override_path) {
+                    Ok(bytes) => {
+                        let leaked: &'static [u8] = Box::leak(bytes.into_boxed_slice());
+                        self.material_override_cache.borrow_mut().insert(key, leaked);
+                        Some(OwnedOrMut::Owned(leaked))
+                    }
+                    Err(error) => {
+                        println!("warning: could not read material override {}: {}", override_path, error);
+                        None
+                    }
+                };
+            }
+        }
+        if let Some(bytes) = texture_bytes_for(texture) {
+            return Some(OwnedOrMut::Owned(bytes));
+        }
+
+        let asset_id = match texture {
+            Material::Decal { asset_id } => asset_id,
+            Material::Texture { asset_id } => asset_id,
+            Material::Custom { asset_id: Some(asset_id), .. } => asset_id,
+            _ => return None,
+        };
+
+        let vtf_bytes = match async_std::task::block_on(assets::fetch_decal_vtf(
+            asset_id,
+            &self.asset_proxy,
+            self.decal_size as u32,
+            &self.asset_cache,
+            self.offline,
+        )) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                println!("warning: could not fetch decal asset {}: {}", asset_id, error);
+                self.report.borrow_mut().record(
+                    SkipCategory::UnresolvedTexture,
+                    format!("asset {}", asset_id),
+                    Some(error.to_string()),
+                );
+                return None;
+            }
+        };
+        // Leaked deliberately: `ConvertOptions::texture_input` returns a
+        // `&'static` slice so it can share a type with the embedded builtin
+        // textures; the process exits shortly after conversion finishes anyway.
+        Some(OwnedOrMut::Owned(Box::leak(vtf_bytes.into_boxed_slice())))
     }
 
     fn texture_output(&mut self, path: &str) -> OwnedOrMut<'_, File> {
@@ -266,10 +469,10 @@ impl<'a> ConvertOptions<&'static [u8], File> for CLIConvertOptions<'a> {
     }
 
     fn skybox_name(&self) -> &str {
-        self.skybox_name
+        &self.skybox_name
     }
 
     fn web_origin(&self) -> &str {
-        ""  // Unused in CLI version; TODO: Remove when async-trait functions are available.
+        &self.asset_proxy
     }
 }
\ No newline at end of file
@@ -0,0 +1,73 @@
+//! Bundles a converted VMF and its generated textures into a single zip,
+//! laid out under the Source `materials/`/`mapsrc/` directory conventions so
+//! it can be dropped straight into a game's content folder. Shared between
+//! the CLI (`--package`) and the WASM build, which always packages since
+//! there's no filesystem to scatter loose files into.
+use std::io::{Seek, Write};
+use std::path::{Path, PathBuf};
+
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+pub const MAPSRC_DIR: &str = "mapsrc";
+pub const MATERIALS_DIR: &str = "materials";
+
+fn zip_options() -> FileOptions {
+    FileOptions::default().compression_method(CompressionMethod::Deflated)
+}
+
+/// Writes `vmf_name` + `vmf_bytes` under `mapsrc/`, and every `(relative_path,
+/// bytes)` texture under `materials/`, into one deflate-compressed zip.
+pub fn build<W: Write + Seek>(
+    writer: W,
+    vmf_name: &str,
+    vmf_bytes: &[u8],
+    textures: impl IntoIterator<Item = (String, Vec<u8>)>,
+) -> zip::result::ZipResult<W> {
+    let mut zip = ZipWriter::new(writer);
+    let options = zip_options();
+
+    zip.start_file(format!("{}/{}", MAPSRC_DIR, vmf_name), options)?;
+    zip.write_all(vmf_bytes)?;
+
+    for (path, bytes) in textures {
+        zip.start_file(format!("{}/{}", MATERIALS_DIR, path), options)?;
+        zip.write_all(&bytes)?;
+    }
+
+    zip.finish()
+}
+
+/// Subdirectory of `--texture-output` that `assets::AssetCache` writes
+/// downloaded decal VTFs into; it's an internal cache, not converter
+/// output, and must not end up in a shipped package.
+pub const ASSET_CACHE_DIR: &str = "asset-cache";
+
+/// Walks `texture_dir` recursively, pairing each file with its path relative
+/// to `texture_dir` so it can be re-rooted under `materials/`. Skips
+/// `ASSET_CACHE_DIR`, which holds cached downloads rather than converter
+/// output.
+pub fn collect_texture_tree(texture_dir: &Path) -> std::io::Result<Vec<(String, Vec<u8>)>> {
+    let mut out = Vec::new();
+    for path in walk(texture_dir, texture_dir)? {
+        let relative = path.strip_prefix(texture_dir).unwrap().to_string_lossy().replace('\\', "/");
+        out.push((relative, std::fs::read(&path)?));
+    }
+    Ok(out)
+}
+
+fn walk(root: &Path, dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if path == root.join(ASSET_CACHE_DIR) {
+                continue;
+            }
+            files.extend(walk(root, &path)?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
@@ -0,0 +1,559 @@
+//! Reader for Roblox's binary `.rbxl`/`.rbxlx`-equivalent place format.
+//!
+//! Rather than teaching `rbx` a second internal representation, a binary file
+//! is decoded into an equivalent RBXLX XML document up front and handed to
+//! the existing XML parser, so everything downstream (`conv::convert`,
+//! `texture_input`, ...) stays untouched.
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Cursor, Read};
+
+use byteorder::{LittleEndian, ReadBytesExt};
+
+/// `<roblox!` + signature bytes, followed by the little-endian u16 format
+/// version (always 0).
+const MAGIC: &[u8; 16] = b"<roblox!\x89\xff\r\n\x1a\n\0\0";
+/// The synthetic referent used for the implicit DataModel root; every
+/// top-level `Item` (Workspace, Lighting, ...) is parented to it.
+const ROOT_REFERENT: i32 = -1;
+
+#[derive(Debug)]
+pub enum BinaryReadError {
+    BadMagic,
+    Truncated(std::io::Error),
+    Decompress(lz4_flex::block::DecompressError),
+    UnknownChunk([u8; 4]),
+}
+
+impl fmt::Display for BinaryReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinaryReadError::BadMagic => write!(f, "not a binary Roblox place file (magic header mismatch)"),
+            BinaryReadError::Truncated(error) => write!(f, "truncated binary place file: {}", error),
+            BinaryReadError::Decompress(error) => write!(f, "could not decompress chunk: {}", error),
+            BinaryReadError::UnknownChunk(tag) => write!(f, "unknown chunk tag {:?}", tag),
+        }
+    }
+}
+
+impl std::error::Error for BinaryReadError {}
+
+impl From<std::io::Error> for BinaryReadError {
+    fn from(error: std::io::Error) -> Self {
+        BinaryReadError::Truncated(error)
+    }
+}
+
+/// True if `data` starts with the binary place file magic header, as opposed
+/// to plain RBXLX XML.
+pub fn is_binary(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+struct ClassRecord {
+    referents: Vec<i32>,
+}
+
+struct Instance {
+    class_name: String,
+    referent: i32,
+    parent: i32,
+    /// Already-rendered `<Properties>` child elements, keyed by property name
+    /// so a later PROP chunk for the same name can't duplicate it.
+    properties: HashMap<String, String>,
+}
+
+/// An instance whose binary `CFrame` rotation couldn't be decoded exactly
+/// (an out-of-range special rotation ID), returned alongside the XML so the
+/// caller can surface it through `Report` instead of silently shipping a
+/// part rotated to identity.
+pub struct UnresolvedRotation {
+    pub referent: i32,
+    pub property: String,
+    pub rotation_id: u8,
+}
+
+/// Decodes a binary place file into an RBXLX XML document string, plus any
+/// `CFrame` columns whose rotation ID fell outside the known special-rotation
+/// table and had to default to identity.
+pub fn decode_to_xml(data: &[u8]) -> Result<(String, Vec<UnresolvedRotation>), BinaryReadError> {
+    if !is_binary(data) {
+        return Err(BinaryReadError::BadMagic);
+    }
+
+    let mut cursor = Cursor::new(&data[MAGIC.len()..]);
+    // num classes / num instances / num unused (reserved) / unused, per the format header.
+    let _num_classes = cursor.read_i32::<LittleEndian>()?;
+    let _num_instances = cursor.read_i32::<LittleEndian>()?;
+    cursor.read_u64::<LittleEndian>()?;
+
+    let mut shared_strings: Vec<Vec<u8>> = Vec::new();
+    let mut classes: HashMap<u32, ClassRecord> = HashMap::new();
+    let mut instances: HashMap<i32, Instance> = HashMap::new();
+    let mut unresolved_rotations: Vec<UnresolvedRotation> = Vec::new();
+
+    loop {
+        let mut tag = [0u8; 4];
+        cursor.read_exact(&mut tag)?;
+        let compressed_len = cursor.read_u32::<LittleEndian>()? as usize;
+        let uncompressed_len = cursor.read_u32::<LittleEndian>()? as usize;
+        cursor.read_u32::<LittleEndian>()?; // reserved, always 0
+
+        let raw = if compressed_len == 0 {
+            let mut bytes = vec![0u8; uncompressed_len];
+            cursor.read_exact(&mut bytes)?;
+            bytes
+        } else {
+            let mut compressed = vec![0u8; compressed_len];
+            cursor.read_exact(&mut compressed)?;
+            lz4_flex::block::decompress(&compressed, uncompressed_len).map_err(BinaryReadError::Decompress)?
+        };
+        let mut body = Cursor::new(raw);
+
+        match &tag {
+            b"META" => {
+                // Key/value metadata pairs; nothing downstream consumes these yet.
+            }
+            b"SSTR" => {
+                body.read_u32::<LittleEndian>()?; // version
+                let count = body.read_u32::<LittleEndian>()?;
+                for _ in 0..count {
+                    body.read_u128::<LittleEndian>()?; // md5 hash, unused here
+                    let len = body.read_u32::<LittleEndian>()? as usize;
+                    let mut bytes = vec![0u8; len];
+                    body.read_exact(&mut bytes)?;
+                    shared_strings.push(bytes);
+                }
+            }
+            b"INST" => {
+                let class_id = body.read_u32::<LittleEndian>()?;
+                let name_len = body.read_u32::<LittleEndian>()? as usize;
+                let mut name_bytes = vec![0u8; name_len];
+                body.read_exact(&mut name_bytes)?;
+                let class_name = String::from_utf8_lossy(&name_bytes).into_owned();
+                body.read_u8()?; // object format (0 = regular, 1 = service)
+                let count = body.read_u32::<LittleEndian>()? as usize;
+                let referents = read_referent_array(&mut body, count)?;
+                for &referent in &referents {
+                    instances.insert(
+                        referent,
+                        Instance { class_name: class_name.clone(), referent, parent: ROOT_REFERENT, properties: HashMap::new() },
+                    );
+                }
+                classes.insert(class_id, ClassRecord { referents });
+            }
+            b"PROP" => {
+                let class_id = body.read_u32::<LittleEndian>()?;
+                let prop_name_len = body.read_u32::<LittleEndian>()? as usize;
+                let mut prop_name_bytes = vec![0u8; prop_name_len];
+                body.read_exact(&mut prop_name_bytes)?;
+                let prop_name = String::from_utf8_lossy(&prop_name_bytes).into_owned();
+                let value_type = body.read_u8()?;
+
+                if let Some(class) = classes.get(&class_id) {
+                    let (rendered, unresolved_indices) =
+                        decode_property_column(&mut body, value_type, class.referents.len(), &shared_strings)?;
+                    for (&referent, xml) in class.referents.iter().zip(rendered) {
+                        if let (Some(instance), Some(xml)) = (instances.get_mut(&referent), xml) {
+                            instance.properties.insert(prop_name.clone(), xml);
+                        }
+                    }
+                    for (index, rotation_id) in unresolved_indices {
+                        if let Some(&referent) = class.referents.get(index) {
+                            unresolved_rotations.push(UnresolvedRotation { referent, property: prop_name.clone(), rotation_id });
+                        }
+                    }
+                }
+            }
+            b"PRNT" => {
+                body.read_u8()?; // version, always 0
+                let count = body.read_u32::<LittleEndian>()? as usize;
+                let objects = read_referent_array(&mut body, count)?;
+                let parents = read_referent_array(&mut body, count)?;
+                for (object, parent) in objects.into_iter().zip(parents) {
+                    if let Some(instance) = instances.get_mut(&object) {
+                        instance.parent = parent;
+                    }
+                }
+            }
+            b"END\0" => break,
+            unknown => return Err(BinaryReadError::UnknownChunk(*unknown)),
+        }
+    }
+
+    Ok((render_xml(&instances), unresolved_rotations))
+}
+
+/// Referent arrays (and other `i32` SoA columns) are stored "interleaved":
+/// each of the 4 bytes of every value is grouped by byte-position rather
+/// than stored value-by-value, then zigzag-encoded; referents are further
+/// delta-encoded from the previous referent in the column.
+fn read_referent_array(cursor: &mut Cursor<Vec<u8>>, count: usize) -> Result<Vec<i32>, BinaryReadError> {
+    let values = read_interleaved_u32(cursor, count)?;
+    let mut previous = 0i32;
+    let mut referents = Vec::with_capacity(count);
+    for raw in values {
+        let delta = zigzag_decode(raw);
+        previous = previous.wrapping_add(delta);
+        referents.push(previous);
+    }
+    Ok(referents)
+}
+
+fn read_interleaved_u32(cursor: &mut Cursor<Vec<u8>>, count: usize) -> Result<Vec<u32>, BinaryReadError> {
+    let mut bytes = vec![0u8; count * 4];
+    cursor.read_exact(&mut bytes)?;
+    let mut values = vec![0u32; count];
+    for byte_index in 0..4 {
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = (*value << 8) | bytes[byte_index * count + i] as u32;
+        }
+    }
+    Ok(values)
+}
+
+fn zigzag_decode(raw: u32) -> i32 {
+    ((raw >> 1) as i32) ^ -((raw & 1) as i32)
+}
+
+/// Floats are stored interleaved like `i32` columns, but bit-rotated (not
+/// zigzagged) so the sign bit sits in the low bit for better compression.
+fn rotate_decode_f32(raw: u32) -> f32 {
+    f32::from_bits((raw >> 1) | (raw << 31))
+}
+
+/// Known property value-type tags, per the documented RBXM/RBXL property
+/// table. Anything not listed here is left undecoded rather than guessed at.
+mod prop_type {
+    pub const STRING: u8 = 0x01;
+    pub const BOOL: u8 = 0x02;
+    pub const INT32: u8 = 0x03;
+    pub const FLOAT: u8 = 0x04;
+    pub const VECTOR3: u8 = 0x0E;
+    pub const CFRAME: u8 = 0x10;
+    pub const ENUM: u8 = 0x12;
+    pub const COLOR3UINT8: u8 = 0x1A;
+    pub const SHARED_STRING: u8 = 0x1F;
+}
+
+/// The 24 axis-aligned rotation matrices a `CFrame` column's rotation ID byte
+/// can index (IDs 1-24 are the ones actually emitted by Studio's grid-snapped
+/// rotate tool; ID 0 means "read 9 raw floats instead" and is handled by the
+/// caller before this table is consulted). These are exactly the rotational
+/// symmetries of a cube — every signed permutation matrix with determinant
+/// +1 — stored row-major, indexed by `id - 1`.
+const SPECIAL_CFRAME_ROTATIONS: [[f32; 9]; 24] = [
+    [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+    [1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, -1.0],
+    [-1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, -1.0],
+    [-1.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0, 0.0, 1.0],
+    [1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, -1.0, 0.0],
+    [1.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, 1.0, 0.0],
+    [-1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0],
+    [-1.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, -1.0, 0.0],
+    [0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, -1.0],
+    [0.0, 1.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+    [0.0, -1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+    [0.0, -1.0, 0.0, -1.0, 0.0, 0.0, 0.0, 0.0, -1.0],
+    [0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0, 0.0, -1.0, -1.0, 0.0, 0.0],
+    [0.0, -1.0, 0.0, 0.0, 0.0, 1.0, -1.0, 0.0, 0.0],
+    [0.0, -1.0, 0.0, 0.0, 0.0, -1.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 1.0, -1.0, 0.0, 0.0, 0.0, -1.0, 0.0],
+    [0.0, 0.0, -1.0, 1.0, 0.0, 0.0, 0.0, -1.0, 0.0],
+    [0.0, 0.0, -1.0, -1.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0, 1.0, 0.0, -1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0, -1.0, 0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, -1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, -1.0, 0.0, -1.0, 0.0, -1.0, 0.0, 0.0],
+];
+
+/// Looks up `id` in `SPECIAL_CFRAME_ROTATIONS`. IDs outside `1..=24` aren't
+/// part of the format; rather than guess, the caller is told via `None` so
+/// it can record a report entry and fall back to identity instead of
+/// silently shipping the wrong rotation.
+fn special_cframe_rotation(id: u8) -> Option<[f32; 9]> {
+    SPECIAL_CFRAME_ROTATIONS.get(id as usize - 1).copied()
+}
+
+/// Decodes one PROP chunk's SoA column into `count` already-rendered
+/// `<Properties>` child elements (one per instance, in class-referent
+/// order), or `None` per-instance where the type isn't supported yet. Also
+/// returns the `(index, rotation_id)` of any `CFrame` entry whose special
+/// rotation ID wasn't in `SPECIAL_CFRAME_ROTATIONS`, so the caller can report it.
+fn decode_property_column(
+    body: &mut Cursor<Vec<u8>>,
+    value_type: u8,
+    count: usize,
+    shared_strings: &[Vec<u8>],
+) -> Result<(Vec<Option<String>>, Vec<(usize, u8)>), BinaryReadError> {
+    match value_type {
+        prop_type::STRING => {
+            let mut out = Vec::with_capacity(count);
+            for _ in 0..count {
+                let len = body.read_u32::<LittleEndian>()? as usize;
+                let mut bytes = vec![0u8; len];
+                body.read_exact(&mut bytes)?;
+                out.push(Some(xml_escape(&String::from_utf8_lossy(&bytes))));
+            }
+            Ok((out, Vec::new()))
+        }
+        prop_type::BOOL => {
+            let mut out = Vec::with_capacity(count);
+            for _ in 0..count {
+                out.push(Some((body.read_u8()? != 0).to_string()));
+            }
+            Ok((out, Vec::new()))
+        }
+        prop_type::INT32 => {
+            let values = read_interleaved_u32(body, count)?;
+            Ok((values.into_iter().map(|raw| Some(zigzag_decode(raw).to_string())).collect(), Vec::new()))
+        }
+        prop_type::ENUM => {
+            // Enums (including Material) are unsigned and not zigzagged.
+            let values = read_interleaved_u32(body, count)?;
+            Ok((values.into_iter().map(|raw| Some(raw.to_string())).collect(), Vec::new()))
+        }
+        prop_type::FLOAT => {
+            let values = read_interleaved_u32(body, count)?;
+            Ok((values.into_iter().map(|raw| Some(rotate_decode_f32(raw).to_string())).collect(), Vec::new()))
+        }
+        prop_type::VECTOR3 => {
+            let xs = read_interleaved_u32(body, count)?;
+            let ys = read_interleaved_u32(body, count)?;
+            let zs = read_interleaved_u32(body, count)?;
+            let out = (0..count)
+                .map(|i| {
+                    Some(format!(
+                        "<X>{}</X><Y>{}</Y><Z>{}</Z>",
+                        rotate_decode_f32(xs[i]),
+                        rotate_decode_f32(ys[i]),
+                        rotate_decode_f32(zs[i])
+                    ))
+                })
+                .collect();
+            Ok((out, Vec::new()))
+        }
+        prop_type::SHARED_STRING => {
+            // An interleaved index into the SSTR chunk's string table, rather
+            // than the string bytes being repeated inline for every instance.
+            let indices = read_interleaved_u32(body, count)?;
+            let out = indices
+                .into_iter()
+                .map(|index| shared_strings.get(index as usize).map(|bytes| xml_escape(&String::from_utf8_lossy(bytes))))
+                .collect();
+            Ok((out, Vec::new()))
+        }
+        prop_type::COLOR3UINT8 => {
+            let mut reds = vec![0u8; count];
+            let mut greens = vec![0u8; count];
+            let mut blues = vec![0u8; count];
+            body.read_exact(&mut reds)?;
+            body.read_exact(&mut greens)?;
+            body.read_exact(&mut blues)?;
+            let out = (0..count)
+                .map(|i| {
+                    let packed = ((reds[i] as u32) << 16) | ((greens[i] as u32) << 8) | blues[i] as u32;
+                    Some(packed.to_string())
+                })
+                .collect();
+            Ok((out, Vec::new()))
+        }
+        prop_type::CFRAME => {
+            let mut rotation_ids = vec![0u8; count];
+            body.read_exact(&mut rotation_ids)?;
+            let mut rotations = Vec::with_capacity(count);
+            let mut unresolved = Vec::new();
+            for (index, &id) in rotation_ids.iter().enumerate() {
+                if id == 0 {
+                    let mut matrix = [0f32; 9];
+                    for value in matrix.iter_mut() {
+                        *value = body.read_f32::<byteorder::BigEndian>()?;
+                    }
+                    rotations.push(matrix);
+                } else {
+                    match special_cframe_rotation(id) {
+                        Some(matrix) => rotations.push(matrix),
+                        None => {
+                            unresolved.push((index, id));
+                            rotations.push(SPECIAL_CFRAME_ROTATIONS[0]);
+                        }
+                    }
+                }
+            }
+            let xs = read_interleaved_u32(body, count)?;
+            let ys = read_interleaved_u32(body, count)?;
+            let zs = read_interleaved_u32(body, count)?;
+            let out = (0..count)
+                .map(|i| {
+                    let m = rotations[i];
+                    Some(format!(
+                        "<X>{}</X><Y>{}</Y><Z>{}</Z><R00>{}</R00><R01>{}</R01><R02>{}</R02><R10>{}</R10><R11>{}</R11><R12>{}</R12><R20>{}</R20><R21>{}</R21><R22>{}</R22>",
+                        rotate_decode_f32(xs[i]), rotate_decode_f32(ys[i]), rotate_decode_f32(zs[i]),
+                        m[0], m[1], m[2], m[3], m[4], m[5], m[6], m[7], m[8]
+                    ))
+                })
+                .collect();
+            Ok((out, unresolved))
+        }
+        _ => {
+            // Unrecognized property type: leave it undecoded rather than guess.
+            Ok((vec![None; count], Vec::new()))
+        }
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// The handful of property names this reader decodes into a non-string type
+/// need their RBXLX element name to match, since the XML schema has a
+/// distinct tag per value type rather than one generic "property" tag.
+fn xml_tag_for(property_name: &str) -> &'static str {
+    match property_name {
+        "CFrame" => "CoordinateFrame",
+        "Size" => "Vector3",
+        "Color" => "Color3uint8",
+        "Material" => "token",
+        _ => "string",
+    }
+}
+
+fn render_xml(instances: &HashMap<i32, Instance>) -> String {
+    let mut children: HashMap<i32, Vec<i32>> = HashMap::new();
+    for instance in instances.values() {
+        children.entry(instance.parent).or_default().push(instance.referent);
+    }
+
+    let mut xml = String::from("<roblox version=\"4\">\n");
+    if let Some(roots) = children.get(&ROOT_REFERENT) {
+        for &referent in roots {
+            render_instance(referent, instances, &children, 1, &mut xml);
+        }
+    }
+    xml.push_str("</roblox>\n");
+    xml
+}
+
+fn render_instance(referent: i32, instances: &HashMap<i32, Instance>, children: &HashMap<i32, Vec<i32>>, depth: usize, xml: &mut String) {
+    let instance = match instances.get(&referent) {
+        Some(instance) => instance,
+        None => return,
+    };
+    let indent = "  ".repeat(depth);
+    xml.push_str(&format!("{}<Item class=\"{}\" referent=\"{}\">\n", indent, instance.class_name, instance.referent));
+    xml.push_str(&format!("{}  <Properties>\n", indent));
+    for (name, value) in &instance.properties {
+        let tag = xml_tag_for(name);
+        xml.push_str(&format!("{}    <{} name=\"{}\">{}</{}>\n", indent, tag, name, value, tag));
+    }
+    xml.push_str(&format!("{}  </Properties>\n", indent));
+    if let Some(child_referents) = children.get(&referent) {
+        for &child in child_referents {
+            render_instance(child, instances, children, depth + 1, xml);
+        }
+    }
+    xml.push_str(&format!("{}</Item>\n", indent));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use byteorder::WriteBytesExt;
+
+    #[test]
+    fn zigzag_decode_matches_the_standard_mapping() {
+        assert_eq!(zigzag_decode(0), 0);
+        assert_eq!(zigzag_decode(1), -1);
+        assert_eq!(zigzag_decode(2), 1);
+        assert_eq!(zigzag_decode(3), -2);
+        assert_eq!(zigzag_decode(4), 2);
+    }
+
+    #[test]
+    fn rotate_decode_f32_round_trips_through_the_inverse_rotation() {
+        for value in [0.0f32, 1.5, -42.25, f32::MIN_POSITIVE] {
+            let raw = value.to_bits().rotate_left(1);
+            assert_eq!(rotate_decode_f32(raw), value);
+        }
+    }
+
+    /// Mirrors the byte layout `read_interleaved_u32` expects: each of the 4
+    /// bytes of every value grouped by byte-position rather than value-by-value.
+    fn interleave_u32(values: &[u32]) -> Vec<u8> {
+        let count = values.len();
+        let mut bytes = vec![0u8; count * 4];
+        for byte_index in 0..4 {
+            let shift = 24 - byte_index * 8;
+            for (i, &value) in values.iter().enumerate() {
+                bytes[byte_index * count + i] = ((value >> shift) & 0xFF) as u8;
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn read_interleaved_u32_round_trips() {
+        let values = vec![0x01020304u32, 0xAABBCCDDu32, 0u32, u32::MAX];
+        let bytes = interleave_u32(&values);
+        let mut cursor = Cursor::new(bytes);
+        let decoded = read_interleaved_u32(&mut cursor, values.len()).unwrap();
+        assert_eq!(decoded, values);
+    }
+
+    fn write_chunk(out: &mut Vec<u8>, tag: &[u8; 4], body: &[u8]) {
+        out.extend_from_slice(tag);
+        out.write_u32::<LittleEndian>(0).unwrap(); // compressed_len: 0 == stored uncompressed
+        out.write_u32::<LittleEndian>(body.len() as u32).unwrap();
+        out.write_u32::<LittleEndian>(0).unwrap(); // reserved
+        out.extend_from_slice(body);
+    }
+
+    /// A minimal binary place file: one `Part` named "TestPart", parented to
+    /// the synthetic DataModel root, exercising INST/PROP/PRNT/END end to end.
+    fn sample_binary_place_file() -> Vec<u8> {
+        let mut file = Vec::new();
+        file.extend_from_slice(MAGIC);
+        file.write_i32::<LittleEndian>(1).unwrap(); // num classes
+        file.write_i32::<LittleEndian>(1).unwrap(); // num instances
+        file.write_u64::<LittleEndian>(0).unwrap(); // reserved
+
+        let mut inst_body = Vec::new();
+        inst_body.write_u32::<LittleEndian>(0).unwrap(); // class_id
+        inst_body.write_u32::<LittleEndian>(4).unwrap(); // name_len
+        inst_body.extend_from_slice(b"Part");
+        inst_body.write_u8(0).unwrap(); // object format: regular
+        inst_body.write_u32::<LittleEndian>(1).unwrap(); // referent count
+        inst_body.extend_from_slice(&interleave_u32(&[0])); // referent 0, delta 0 from previous
+        write_chunk(&mut file, b"INST", &inst_body);
+
+        let mut prop_body = Vec::new();
+        prop_body.write_u32::<LittleEndian>(0).unwrap(); // class_id
+        prop_body.write_u32::<LittleEndian>(4).unwrap(); // prop_name_len
+        prop_body.extend_from_slice(b"Name");
+        prop_body.write_u8(prop_type::STRING).unwrap();
+        prop_body.write_u32::<LittleEndian>(8).unwrap(); // value length
+        prop_body.extend_from_slice(b"TestPart");
+        write_chunk(&mut file, b"PROP", &prop_body);
+
+        let mut prnt_body = Vec::new();
+        prnt_body.write_u8(0).unwrap(); // version
+        prnt_body.write_u32::<LittleEndian>(1).unwrap(); // count
+        prnt_body.extend_from_slice(&interleave_u32(&[0])); // object referent 0, delta 0
+        prnt_body.extend_from_slice(&interleave_u32(&[1])); // parent referent -1 (ROOT_REFERENT), zigzag delta -1 encodes as raw 1
+        write_chunk(&mut file, b"PRNT", &prnt_body);
+
+        write_chunk(&mut file, b"END\0", &[]);
+        file
+    }
+
+    #[test]
+    fn decode_to_xml_transposes_properties_and_nests_by_parent() {
+        let (xml, unresolved_rotations) = decode_to_xml(&sample_binary_place_file()).unwrap();
+        assert!(unresolved_rotations.is_empty());
+        assert!(xml.contains("<Item class=\"Part\" referent=\"0\">"));
+        assert!(xml.contains("<string name=\"Name\">TestPart</string>"));
+    }
+}
@@ -0,0 +1,83 @@
+//! Tracks instances the converter could not translate, so a user can see
+//! exactly what was lost instead of the map just looking wrong in-engine.
+//!
+//! Every variant here is recorded from code that actually exists in this
+//! tree: `CLIConvertOptions::texture_input` (main.rs) records
+//! `UnresolvedTexture` when a decal/texture asset can't be resolved, and
+//! `rbx_binary::decode_to_xml`'s caller records `UnresolvedRotation` when a
+//! binary `CFrame`'s special rotation ID isn't recognized. `conv::convert` is
+//! where MeshParts, unions/CSG, Terrain, and unsupported-angle WedgeParts are
+//! actually dropped today, but that module isn't part of this tree, so this
+//! report can't add categories for them without guessing at code it can't
+//! see -- add those variants (and their `record()` call sites) alongside
+//! whatever change next touches `conv::convert` directly.
+use std::collections::BTreeMap;
+use std::io::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SkipCategory {
+    UnresolvedTexture,
+    UnresolvedRotation,
+}
+
+impl SkipCategory {
+    fn label(self) -> &'static str {
+        match self {
+            SkipCategory::UnresolvedTexture => "textures with unresolved asset IDs",
+            SkipCategory::UnresolvedRotation => "binary CFrames with an unrecognized special rotation ID",
+        }
+    }
+}
+
+pub struct SkippedInstance {
+    pub category: SkipCategory,
+    pub path: String,
+    pub detail: Option<String>,
+}
+
+#[derive(Default)]
+pub struct Report {
+    skipped: Vec<SkippedInstance>,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Report::default()
+    }
+
+    pub fn record(&mut self, category: SkipCategory, path: impl Into<String>, detail: Option<String>) {
+        self.skipped.push(SkippedInstance { category, path: path.into(), detail });
+    }
+
+    pub fn write_summary(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        if self.skipped.is_empty() {
+            return writeln!(out, "conversion report: nothing was skipped");
+        }
+        let mut counts: BTreeMap<&'static str, usize> = BTreeMap::new();
+        for skip in &self.skipped {
+            *counts.entry(skip.category.label()).or_insert(0) += 1;
+        }
+        writeln!(out, "conversion report: {} instance(s) could not be translated", self.skipped.len())?;
+        for (label, count) in counts {
+            writeln!(out, "  {}: {}", label, count)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_json(&self, out: &mut dyn Write) -> serde_json::Result<()> {
+        let mut by_category: BTreeMap<&'static str, Vec<serde_json::Value>> = BTreeMap::new();
+        for skip in &self.skipped {
+            by_category.entry(skip.category.label()).or_default().push(serde_json::json!({
+                "path": skip.path,
+                "detail": skip.detail,
+            }));
+        }
+        let manifest = serde_json::json!({
+            "total_skipped": self.skipped.len(),
+            "categories": by_category.into_iter().map(|(label, instances)| {
+                serde_json::json!({ "category": label, "count": instances.len(), "instances": instances })
+            }).collect::<Vec<_>>(),
+        });
+        serde_json::to_writer_pretty(out, &manifest)
+    }
+}
@@ -0,0 +1,39 @@
+//! Minimal Valve Texture Format (VTF) writer.
+//!
+//! Only covers what a downloaded decal needs: a single uncompressed RGBA8888
+//! frame with no mipmaps. The builtin materials in `rbx::textures` ship as
+//! pre-baked VTF files and never go through this path.
+const VTF_SIGNATURE: &[u8; 4] = b"VTF\0";
+const IMAGE_FORMAT_RGBA8888: u32 = 0;
+
+/// Encodes a single RGBA8888 image as a VTF 7.1 file.
+pub fn encode_rgba8888(width: u16, height: u16, rgba: &[u8]) -> Vec<u8> {
+    debug_assert_eq!(rgba.len(), width as usize * height as usize * 4);
+
+    let mut out = Vec::with_capacity(80 + rgba.len());
+    out.extend_from_slice(VTF_SIGNATURE);
+    out.extend_from_slice(&7u32.to_le_bytes()); // version major
+    out.extend_from_slice(&1u32.to_le_bytes()); // version minor
+    out.extend_from_slice(&80u32.to_le_bytes()); // header size
+    out.extend_from_slice(&width.to_le_bytes());
+    out.extend_from_slice(&height.to_le_bytes());
+    out.extend_from_slice(&0u32.to_le_bytes()); // flags
+    out.extend_from_slice(&1u16.to_le_bytes()); // frames
+    out.extend_from_slice(&0u16.to_le_bytes()); // first frame
+    out.extend_from_slice(&[0u8; 4]); // padding
+    for _ in 0..3 {
+        out.extend_from_slice(&0f32.to_le_bytes()); // reflectivity (r, g, b)
+    }
+    out.extend_from_slice(&[0u8; 4]); // padding
+    out.extend_from_slice(&0f32.to_le_bytes()); // bumpmap scale
+    out.extend_from_slice(&IMAGE_FORMAT_RGBA8888.to_le_bytes());
+    out.push(1); // mipmap count
+    out.extend_from_slice(&u32::MAX.to_le_bytes()); // low-res (thumbnail) format: none
+    out.extend_from_slice(&0u8.to_le_bytes()); // low-res width
+    out.extend_from_slice(&0u8.to_le_bytes()); // low-res height
+    out.extend_from_slice(&1u16.to_le_bytes()); // depth: single layer, not a volume texture
+    out.resize(80, 0); // pad out the remainder of the fixed header
+
+    out.extend_from_slice(rgba);
+    out
+}
@@ -0,0 +1,239 @@
+//! WASM/browser entry point. Mirrors `CLIConvertOptions` in main.rs but keeps
+//! everything in memory: input comes from a `<input type="file">` element read
+//! client-side into a `String`, output is zipped up and handed back to JS for
+//! download instead of touching a filesystem.
+#![cfg(target_arch = "wasm32")]
+
+use std::collections::HashMap;
+use std::io::{Cursor, Write};
+
+use wasm_bindgen::prelude::*;
+
+use crate::conv::{self, ConvertOptions, OwnedOrMut, OwnedOrRef};
+use crate::game_profile::GameProfile;
+use crate::rbx::Material;
+
+/// A `Write` sink that forwards completed lines to a DOM log element via
+/// `console.log` plus a JS callback, since there's no stdout/stderr in the browser.
+struct DomLogWriter {
+    callback: js_sys::Function,
+    buffer: Vec<u8>,
+}
+
+impl DomLogWriter {
+    fn new(callback: js_sys::Function) -> Self {
+        DomLogWriter { callback, buffer: Vec::new() }
+    }
+
+    fn flush_line(&mut self, line: &str) {
+        web_sys::console::log_1(&JsValue::from_str(line));
+        let _ = self.callback.call1(&JsValue::NULL, &JsValue::from_str(line));
+    }
+}
+
+impl Write for DomLogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            self.flush_line(String::from_utf8_lossy(&line).trim_end());
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if !self.buffer.is_empty() {
+            let line = String::from_utf8_lossy(&self.buffer).into_owned();
+            self.buffer.clear();
+            self.flush_line(&line);
+        }
+        Ok(())
+    }
+}
+
+pub struct WasmConvertOptions {
+    input_name: String,
+    input_data: String,
+    web_origin: String,
+    log_callback: js_sys::Function,
+    error_callback: js_sys::Function,
+    vmf_output: Cursor<Vec<u8>>,
+    texture_outputs: HashMap<String, Cursor<Vec<u8>>>,
+    is_texture_output_enabled: bool,
+    use_developer_textures: bool,
+    map_scale: f64,
+    auto_skybox_enabled: bool,
+    skybox_clearance: f64,
+    optimization_enabled: bool,
+    decal_size: u64,
+    skybox_name: String,
+}
+
+impl ConvertOptions<&'static [u8], Cursor<Vec<u8>>> for WasmConvertOptions {
+    fn print_output(&self) -> Box<dyn Write> {
+        Box::new(DomLogWriter::new(self.log_callback.clone()))
+    }
+    fn error_output(&self) -> Box<dyn Write> {
+        Box::new(DomLogWriter::new(self.error_callback.clone()))
+    }
+
+    fn input_name(&self) -> &str {
+        &self.input_name
+    }
+
+    fn read_input_data(&self) -> OwnedOrRef<'_, String> {
+        OwnedOrRef::Ref(&self.input_data)
+    }
+
+    fn vmf_output(&mut self) -> OwnedOrMut<'_, Cursor<Vec<u8>>> {
+        OwnedOrMut::Ref(&mut self.vmf_output)
+    }
+
+    fn texture_input(&mut self, texture: Material) -> Option<OwnedOrMut<'_, &'static [u8]>> {
+        if let Some(bytes) = crate::texture_bytes_for(texture) {
+            return Some(OwnedOrMut::Owned(bytes));
+        }
+
+        let asset_id = match texture {
+            Material::Decal { asset_id } => asset_id,
+            Material::Texture { asset_id } => asset_id,
+            Material::Custom { asset_id: Some(asset_id), .. } => asset_id,
+            _ => return None,
+        };
+
+        match fetch_decal_vtf_sync(asset_id, &self.web_origin, self.decal_size as u32) {
+            Ok(bytes) => Some(OwnedOrMut::Owned(Box::leak(bytes.into_boxed_slice()))),
+            Err(error) => {
+                let mut writer = DomLogWriter::new(self.error_callback.clone());
+                let _ = writeln!(writer, "warning: could not fetch decal asset {}: {}", asset_id, error);
+                None
+            }
+        }
+    }
+
+    fn texture_output(&mut self, path: &str) -> OwnedOrMut<'_, Cursor<Vec<u8>>> {
+        OwnedOrMut::Ref(
+            self.texture_outputs
+                .entry(path.to_owned())
+                .or_insert_with(|| Cursor::new(Vec::new())),
+        )
+    }
+
+    fn texture_output_enabled(&self) -> bool {
+        self.is_texture_output_enabled
+    }
+
+    fn use_dev_textures(&self) -> bool {
+        self.use_developer_textures
+    }
+
+    fn map_scale(&self) -> f64 {
+        self.map_scale
+    }
+
+    fn auto_skybox_enabled(&self) -> bool {
+        self.auto_skybox_enabled
+    }
+
+    fn skybox_clearance(&self) -> f64 {
+        self.skybox_clearance
+    }
+
+    fn optimization_enabled(&self) -> bool {
+        self.optimization_enabled
+    }
+
+    fn decal_size(&self) -> u64 {
+        self.decal_size
+    }
+
+    fn skybox_name(&self) -> &str {
+        &self.skybox_name
+    }
+
+    fn web_origin(&self) -> &str {
+        &self.web_origin
+    }
+}
+
+/// Resolves `asset_id` through the Roblox asset-delivery endpoint (fronted by
+/// `cors_proxy_base`, since the delivery endpoint doesn't send CORS headers),
+/// resizes it to `decal_size` square, and returns it encoded as a VTF.
+/// `ConvertOptions::texture_input` is synchronous, so this blocks the page
+/// with a synchronous `XMLHttpRequest` rather than awaiting a `fetch()` --
+/// there's no async equivalent available to a sync trait method in a
+/// single-threaded browser, mirroring how the CLI build blocks on
+/// `async_std::task::block_on` around the same kind of fetch (see
+/// `assets::fetch_decal_vtf`). Unlike the CLI build there's no on-disk cache.
+fn fetch_decal_vtf_sync(asset_id: u64, cors_proxy_base: &str, decal_size: u32) -> Result<Vec<u8>, String> {
+    let url = format!("{}https://assetdelivery.roblox.com/v1/asset/?id={}", cors_proxy_base, asset_id);
+    let bytes = fetch_bytes_sync(&url)?;
+    let image = image::load_from_memory(&bytes).map_err(|error| error.to_string())?;
+    let resized = image.resize_exact(decal_size, decal_size, image::imageops::FilterType::Lanczos3);
+    let rgba = resized.to_rgba8();
+    Ok(crate::vtf::encode_rgba8888(decal_size as u16, decal_size as u16, rgba.as_raw()))
+}
+
+/// Blocking GET via a synchronous `XMLHttpRequest` -- deprecated, but the
+/// only way to get synchronous HTTP out of a single-threaded page.
+fn fetch_bytes_sync(url: &str) -> Result<Vec<u8>, String> {
+    let xhr = web_sys::XmlHttpRequest::new().map_err(|error| format!("{:?}", error))?;
+    xhr.open_with_async("GET", url, false).map_err(|error| format!("{:?}", error))?;
+    xhr.set_response_type(web_sys::XmlHttpRequestResponseType::Arraybuffer);
+    xhr.send().map_err(|error| format!("{:?}", error))?;
+    if xhr.status().unwrap_or(0) != 200 {
+        return Err(format!("HTTP {}", xhr.status().unwrap_or(0)));
+    }
+    let response = xhr.response().map_err(|error| format!("{:?}", error))?;
+    Ok(js_sys::Uint8Array::new(&response).to_vec())
+}
+
+/// Runs the converter entirely in-browser and resolves with a zip archive
+/// (as a `Uint8Array`) containing the VMF plus any generated textures.
+#[wasm_bindgen]
+pub fn convert_rbxlx(
+    input_name: String,
+    input_data: String,
+    web_origin: String,
+    game: String,
+    map_scale: f64,
+    decal_size: u64,
+    auto_skybox_enabled: bool,
+    optimization_enabled: bool,
+    log_callback: js_sys::Function,
+    error_callback: js_sys::Function,
+) -> js_sys::Promise {
+    let skybox_name = GameProfile::builtin(&game).map(|profile| profile.skybox_name).unwrap_or_else(|| "default_skybox_fixme".to_owned());
+    let mut options = WasmConvertOptions {
+        input_name,
+        input_data,
+        web_origin,
+        log_callback,
+        error_callback,
+        vmf_output: Cursor::new(Vec::new()),
+        texture_outputs: HashMap::new(),
+        is_texture_output_enabled: true,
+        use_developer_textures: false,
+        map_scale,
+        auto_skybox_enabled,
+        skybox_clearance: 0f64,
+        optimization_enabled,
+        decal_size,
+        skybox_name,
+    };
+
+    wasm_bindgen_futures::future_to_promise(async move {
+        conv::convert(&mut options)
+            .await
+            .map_err(|error| JsValue::from_str(&error.to_string()))?;
+        let textures = options
+            .texture_outputs
+            .iter()
+            .map(|(path, data)| (path.clone(), data.get_ref().clone()))
+            .collect::<Vec<_>>();
+        let archive = crate::package::build(Cursor::new(Vec::new()), "rbxlx_out.vmf", options.vmf_output.get_ref(), textures)
+            .map_err(|error| JsValue::from_str(&error.to_string()))?
+            .into_inner();
+        Ok(JsValue::from(js_sys::Uint8Array::from(archive.as_slice())))
+    })
+}